@@ -1,3 +1,74 @@
+fn print_entry(
+    entry: &neotron_romfs::Entry<&str, &[u8]>,
+    full_path: &str,
+    unpack_name: Option<&str>,
+) -> Result<(), std::io::Error> {
+    let time_str = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        entry.metadata.ctime.year_since_1970 as u32 + 1970,
+        entry.metadata.ctime.zero_indexed_month + 1,
+        entry.metadata.ctime.zero_indexed_day + 1,
+        entry.metadata.ctime.hours,
+        entry.metadata.ctime.minutes,
+        entry.metadata.ctime.seconds,
+    );
+    println!(
+        "Found name={:?}, ctime={}, size={}",
+        entry.metadata.file_name, time_str, entry.metadata.file_size
+    );
+    if let Some(unpack_name) = unpack_name {
+        if full_path == unpack_name {
+            std::fs::write(entry.metadata.file_name, entry.contents)?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists one directory level, recursing into any sub-directories so the
+/// whole tree gets printed.
+///
+/// `unpack_name` is matched against each entry's full slash-separated path
+/// (as accepted by [`neotron_romfs::RomFs::find`]), not just its bare file
+/// name, so unpacking can't be fooled by two directories containing a
+/// same-named file.
+fn list_dir(
+    r: &neotron_romfs::RomFs,
+    path: &str,
+    unpack_name: Option<&str>,
+) -> Result<(), std::io::Error> {
+    let Some(dir_iter) = r.open_dir(path) else {
+        return Ok(());
+    };
+    for entry in dir_iter {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("Error unpacking ROMFS: {:?}", e);
+                break;
+            }
+        };
+        match entry {
+            neotron_romfs::DirEntry::File(entry) => {
+                let full_path = if path.is_empty() {
+                    entry.metadata.file_name.to_string()
+                } else {
+                    format!("{}/{}", path, entry.metadata.file_name)
+                };
+                print_entry(&entry, &full_path, unpack_name)?
+            }
+            neotron_romfs::DirEntry::Directory { metadata, .. } => {
+                let child_path = if path.is_empty() {
+                    metadata.file_name.to_string()
+                } else {
+                    format!("{}/{}", path, metadata.file_name)
+                };
+                list_dir(r, &child_path, unpack_name)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), std::io::Error> {
     let mut args = std::env::args_os().skip(1);
     let Some(romfs_path) = args.next() else {
@@ -13,33 +84,7 @@ fn main() -> Result<(), std::io::Error> {
         }
     };
 
-    for entry in &r {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(e) => {
-                eprintln!("Error unpacking ROMFS: {:?}", e);
-                break;
-            }
-        };
-        let time_str = format!(
-            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-            entry.metadata.ctime.year_since_1970 as u32 + 1970,
-            entry.metadata.ctime.zero_indexed_month + 1,
-            entry.metadata.ctime.zero_indexed_day + 1,
-            entry.metadata.ctime.hours,
-            entry.metadata.ctime.minutes,
-            entry.metadata.ctime.seconds,
-        );
-        println!(
-            "Found name={:?}, ctime={}, size={}",
-            entry.metadata.file_name, time_str, entry.metadata.file_size
-        );
-        if let Some(unpack_name) = unpack_name.as_deref() {
-            if entry.metadata.file_name == unpack_name {
-                std::fs::write(unpack_name, entry.contents)?;
-            }
-        }
-    }
+    list_dir(&r, "", unpack_name.as_deref())?;
 
     Ok(())
 }
@@ -2,6 +2,7 @@
 //!
 //! * Takes a series of command-line arguments, which should each be a path to file.
 //! * Writes a valid ROMFS image to `stdout`, containing all those files.
+//! * Pass `--compress` to LZSS-compress each file's contents in the image.
 //!
 //! ```console
 //! $ cargo run --bin neotron-romfs-mkfs Cargo.toml LICENSE-MIT > image.rom
@@ -22,8 +23,18 @@ use chrono::{Datelike, Timelike};
 
 /// Entry point to the binary
 fn main() -> Result<(), std::io::Error> {
+    let mut compress = false;
+    let mut file_paths = Vec::new();
+    for arg in std::env::args_os().skip(1) {
+        if arg == "--compress" {
+            compress = true;
+        } else {
+            file_paths.push(arg);
+        }
+    }
+
     let mut entries = Vec::new();
-    for file_path in std::env::args_os().skip(1) {
+    for file_path in &file_paths {
         let file_path: &std::path::Path = file_path.as_ref();
         eprintln!("Loading {}", file_path.display());
         let contents = std::fs::read(file_path)?;
@@ -36,7 +47,18 @@ fn main() -> Result<(), std::io::Error> {
         let metadata = std::fs::metadata(file_path)?;
         let ctime = metadata.created().unwrap_or(std::time::SystemTime::now());
         let ctime = chrono::DateTime::<chrono::Utc>::from(ctime);
-        let entry = neotron_romfs::Entry {
+        let uncompressed_size = contents.len() as u32;
+        let (compression, contents) = if compress {
+            let mut compressed = vec![0u8; contents.len() + contents.len() / 8 + 16];
+            let n = neotron_romfs::lzss::compress_into(&contents, &mut compressed)
+                .expect("compressed buffer was sized generously enough");
+            compressed.truncate(n);
+            (neotron_romfs::Compression::Lzss, compressed)
+        } else {
+            (neotron_romfs::Compression::None, contents)
+        };
+        let content_crc = neotron_romfs::crc32::crc32(&contents);
+        let entry = neotron_romfs::BuildEntry::File {
             metadata: neotron_romfs::EntryMetadata {
                 file_name: file_name_str.to_owned(),
                 ctime: neotron_api::file::Time {
@@ -48,6 +70,9 @@ fn main() -> Result<(), std::io::Error> {
                     seconds: ctime.second() as u8,
                 },
                 file_size: contents.len() as u32,
+                compression,
+                uncompressed_size,
+                content_crc,
             },
             contents,
         };
@@ -55,8 +80,8 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     // make this plenty big enough
-    let mut output: Vec<u8> = vec![0u8; neotron_romfs::RomFs::size_required(&entries)];
-    match neotron_romfs::RomFs::construct(&mut output, &entries) {
+    let mut output: Vec<u8> = vec![0u8; neotron_romfs::RomFs::size_required_tree(&entries)];
+    match neotron_romfs::RomFs::construct_tree(&mut output, &entries) {
         Ok(n) => {
             let valid = &output[0..n];
             let mut out = std::io::stdout();
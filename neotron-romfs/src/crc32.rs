@@ -0,0 +1,83 @@
+//! A small, table-free bitwise CRC-32 implementation.
+//!
+//! Used to checksum a whole ROMFS image and individual entries' stored
+//! contents (see [`crate::FormatVersion::Version400`]). Uses the same
+//! parameters as the CRC-32 found in `zip` and `gzip`: the reflected IEEE
+//! polynomial `0xEDB88320`, an initial value of `0xFFFFFFFF`, and a final
+//! XOR of `0xFFFFFFFF`.
+
+/// The (reflected) IEEE 802.3 CRC-32 polynomial.
+const POLY: u32 = 0xEDB8_8320;
+
+/// Running CRC-32 state, so data can be checksummed incrementally as it
+/// streams past, rather than needing it all in memory at once.
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    /// Start a new CRC-32 calculation.
+    pub fn new() -> Self {
+        Crc32 {
+            state: 0xFFFF_FFFF,
+        }
+    }
+
+    /// Fold more bytes into the running checksum.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state ^= byte as u32;
+            for _ in 0..8 {
+                // a set low bit means "subtract" the polynomial, which for
+                // a reflected CRC means XOR-ing it in after shifting right
+                let mask = (self.state & 1).wrapping_neg();
+                self.state = (self.state >> 1) ^ (POLY & mask);
+            }
+        }
+    }
+
+    /// Finish the calculation, returning the final CRC-32 value.
+    pub fn finish(self) -> u32 {
+        self.state ^ 0xFFFF_FFFF
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-32 of `data` in one call.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_check_value() {
+        // "123456789" is the standard CRC-32 check string.
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn incremental_matches_one_shot() {
+        let mut crc = Crc32::new();
+        crc.update(b"abc");
+        crc.update(b"def");
+        assert_eq!(crc.finish(), crc32(b"abcdef"));
+    }
+}
+
+// End of file
@@ -5,8 +5,8 @@
 //! ```rust
 //! fn process_rom(data: &[u8]) -> Result<(), neotron_romfs::Error> {
 //!     let romfs = neotron_romfs::RomFs::new(data)?;
-//!     for entry in romfs {
-//!         if let Ok(entry) = entry {
+//!     for entry in &romfs {
+//!         if let Ok(neotron_romfs::DirEntry::File(entry)) = entry {
 //!            println!("{} is {} bytes", entry.metadata.file_name, entry.metadata.file_size);
 //!         }
 //!     }
@@ -14,11 +14,13 @@
 //! }
 //! ```
 //!
-//! To open a specific file, use [`RomFs::find`]:
+//! To open a specific file, use [`RomFs::find`]. Paths are slash-separated,
+//! so nested files (in images built with [`FormatVersion::Version200`]) can
+//! be opened the same way as top-level ones:
 //!
 //! ```rust
 //! fn process_rom(romfs: &neotron_romfs::RomFs) {
-//!     if let Some(entry) = romfs.find("HELLO.ELF") {
+//!     if let Some(entry) = romfs.find("BIN/HELLO.ELF") {
 //!         let data: &[u8] = entry.contents;
 //!     }
 //! }
@@ -26,6 +28,11 @@
 
 #![no_std]
 
+use embedded_io::{Read, Seek, Write};
+
+pub mod crc32;
+pub mod lzss;
+
 /// The ways in which this module can fail
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Error {
@@ -37,46 +44,181 @@ pub enum Error {
     UnknownVersion,
     /// Buffer was too small to hold ROMFS image
     BufferTooSmall,
-    /// Filename was too long (we have a 14 byte maximum)
+    /// Filename was too long
+    ///
+    /// A [`FormatVersion::Version200`] (or later) entry can carry a long
+    /// filename as an extension record (its 14-byte short name field then
+    /// holds a truncated copy), but the extension's own 2-byte length
+    /// prefix still caps the name at 65535 bytes - see
+    /// [`EntryMetadata::LONG_NAME_FLAG`]. A
+    /// [`FormatVersion::Version100`] entry, which has no tag byte to carry
+    /// that flag, is stuck with the 14-byte limit outright.
     FilenameTooLong,
     /// A filename wasn't valid UTF-8
     NonUnicodeFilename,
     /// There was an error writing to a sink
     SinkError,
+    /// There was an error reading from, or seeking, a source
+    SourceError,
+    /// A compressed entry's contents could not be decompressed
+    DecompressError,
+    /// A CRC-32 stored in the image didn't match the data it was supposed
+    /// to cover
+    ChecksumMismatch,
+    /// An entry asked for a compression scheme the target format can't
+    /// represent
+    ///
+    /// [`FormatVersion::Version100`] has no compression tag byte at all, so
+    /// [`RomFs::construct`] and [`RomFs::construct_into`] reject any entry
+    /// whose `metadata.compression` isn't [`Compression::None`] - build a
+    /// [`FormatVersion::Version300`] (or later) image with
+    /// [`RomFs::construct_tree`] instead.
+    UnsupportedCompression,
 }
 
 /// The different image formats we support
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FormatVersion {
-    /// The first version
+    /// The first version - a flat list of files, one after another
     Version100 = 1,
+    /// Adds hierarchical directories - each entry is now tagged as a file
+    /// or a directory, and a directory's contents are a nested run of
+    /// entries of their own.
+    ///
+    /// Also the first version whose tag byte can carry
+    /// [`EntryMetadata::LONG_NAME_FLAG`], letting an entry's name run past
+    /// the usual 14-byte limit via a trailing extension record.
+    Version200 = 2,
+    /// Adds per-entry transparent compression - each entry now also carries
+    /// a [`Compression`] tag and an `uncompressed_size`, so a file's stored
+    /// bytes may need decompressing to recover its original contents.
+    Version300 = 3,
+    /// Adds integrity checking - the header now carries a CRC-32 of
+    /// everything after it, and each entry also carries a CRC-32 of its
+    /// stored contents. See [`RomFs::new_verified`] and [`Entry::verify`].
+    Version400 = 4,
+}
+
+/// The compression scheme (if any) used to store an entry's contents.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Compression {
+    /// Contents are stored verbatim.
+    None = 0,
+    /// Contents are compressed with our built-in LZSS codec - see the
+    /// `lzss` module for the on-disk stream format.
+    Lzss = 1,
+}
+
+impl TryFrom<u8> for Compression {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Lzss),
+            _ => Err(Error::UnknownVersion),
+        }
+    }
 }
 
 /// Represents a ROM Filing System (ROMFS), as backed by a byte slice in memory.
+#[derive(Debug)]
 pub struct RomFs<'a> {
+    format_version: FormatVersion,
     contents: &'a [u8],
 }
 
 impl<'a> RomFs<'a> {
     /// Mount a ROMFS using a given block of RAM
     pub fn new(contents: &'a [u8]) -> Result<RomFs<'a>, Error> {
+        Self::new_checked(contents, false)
+    }
+
+    /// Mount a ROMFS using a given block of RAM, additionally verifying its
+    /// whole-image CRC-32.
+    ///
+    /// Images older than [`FormatVersion::Version400`] don't carry a
+    /// whole-image CRC-32 at all, so for those this is equivalent to
+    /// [`Self::new`] - there's simply nothing to check.
+    pub fn new_verified(contents: &'a [u8]) -> Result<RomFs<'a>, Error> {
+        Self::new_checked(contents, true)
+    }
+
+    /// Shared implementation of [`Self::new`] and [`Self::new_verified`].
+    fn new_checked(contents: &'a [u8], verify: bool) -> Result<RomFs<'a>, Error> {
         let (header, remainder) = Header::from_bytes(contents)?;
         if contents.len() != header.total_size as usize {
             return Err(Error::WrongSize);
         }
+        if verify {
+            if let Some(expected_crc) = header.image_crc {
+                if crc32::crc32(remainder) != expected_crc {
+                    return Err(Error::ChecksumMismatch);
+                }
+            }
+        }
         Ok(RomFs {
+            format_version: header.format_version,
             contents: remainder,
         })
     }
 
-    /// Find a file in the ROMFS, by name.
-    pub fn find(&self, file_name: &str) -> Option<Entry<&str, &[u8]>> {
-        self.into_iter()
-            .flatten()
-            .find(|e| e.metadata.file_name == file_name)
+    /// Find a file in the ROMFS, by path.
+    ///
+    /// The path is a slash-separated list of directory names, ending in the
+    /// file name, e.g. `"BIN/HELLO.ELF"`. A path with no slashes is looked
+    /// up in the root directory, which is the only kind of lookup that
+    /// [`FormatVersion::Version100`] images support.
+    pub fn find(&self, path: &str) -> Option<Entry<&'a str, &'a [u8]>> {
+        let (dir_path, file_name) = match path.rfind('/') {
+            Some(idx) => (&path[..idx], &path[idx + 1..]),
+            None => ("", path),
+        };
+        let dir_iter = if dir_path.is_empty() {
+            DirIter(RomFsEntryIter {
+                contents: self.contents,
+                format_version: self.format_version,
+            })
+        } else {
+            self.open_dir(dir_path)?
+        };
+        dir_iter.flatten().find_map(|entry| match entry {
+            DirEntry::File(entry) if entry.metadata.file_name == file_name => Some(entry),
+            _ => None,
+        })
     }
 
-    /// Construct a ROMFS into the given buffer.
+    /// Open a directory in the ROMFS, by path.
+    ///
+    /// The path is a slash-separated list of directory names, e.g.
+    /// `"BIN/SUBDIR"`. Pass an empty string (or any string of just slashes)
+    /// to open the root directory.
+    ///
+    /// Returns `None` if any component of the path doesn't exist, or isn't
+    /// a directory.
+    pub fn open_dir(&self, path: &str) -> Option<DirIter<'a>> {
+        let mut contents = self.contents;
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            let iter = RomFsEntryIter {
+                contents,
+                format_version: self.format_version,
+            };
+            let children = iter.flatten().find_map(|entry| match entry {
+                DirEntry::Directory { metadata, children } if metadata.file_name == component => {
+                    Some(children)
+                }
+                _ => None,
+            })?;
+            contents = children;
+        }
+        Some(DirIter(RomFsEntryIter {
+            contents,
+            format_version: self.format_version,
+        }))
+    }
+
+    /// Construct a flat (non-hierarchical) ROMFS into the given buffer.
     ///
     /// Tells you how many bytes it used of the given buffer.
     ///
@@ -95,7 +237,13 @@ impl<'a> RomFs<'a> {
         Ok(used)
     }
 
-    /// Construct a ROMFS into the given embedded-io byte sink.
+    /// Construct a flat (non-hierarchical) ROMFS into the given embedded-io
+    /// byte sink, as a [`FormatVersion::Version100`] image.
+    ///
+    /// `FormatVersion::Version100` predates both compression and CRC-32
+    /// checking, so this returns [`Error::UnsupportedCompression`] if any
+    /// entry's `metadata.compression` isn't [`Compression::None`] - use
+    /// [`RomFs::construct_tree_into`] if you need either feature.
     ///
     /// Tells you how many bytes it wrote to the given buffer.
     pub fn construct_into<S, T, SINK>(
@@ -107,10 +255,17 @@ impl<'a> RomFs<'a> {
         T: AsRef<[u8]>,
         SINK: embedded_io::Write,
     {
+        for entry in entries.iter() {
+            if entry.metadata.compression != Compression::None {
+                return Err(Error::UnsupportedCompression);
+            }
+        }
+
         let total_size = Self::size_required(entries);
         let file_header = Header {
             format_version: FormatVersion::Version100,
             total_size: total_size as u32,
+            image_crc: None,
         };
         let mut used = file_header.write_into(buffer)?;
         for entry in entries.iter() {
@@ -125,7 +280,7 @@ impl<'a> RomFs<'a> {
         Ok(total_size)
     }
 
-    /// Tells you how many bytes you need to make a ROMFS from these entries.
+    /// Tells you how many bytes you need to make a flat ROMFS from these entries.
     pub fn size_required<S, T>(entries: &[Entry<S, T>]) -> usize
     where
         S: AsRef<str>,
@@ -139,55 +294,425 @@ impl<'a> RomFs<'a> {
         }
         total_size
     }
+
+    /// Construct a hierarchical (directory-aware) ROMFS into the given buffer.
+    ///
+    /// Tells you how many bytes it used of the given buffer.
+    ///
+    /// The buffer must be large enough otherwise an error is returned - see
+    /// [`Self::size_required_tree`] to calculate the size of buffer required.
+    pub fn construct_tree<S, T>(
+        mut buffer: &mut [u8],
+        entries: &[BuildEntry<S, T>],
+    ) -> Result<usize, Error>
+    where
+        S: AsRef<str>,
+        T: AsRef<[u8]>,
+    {
+        let total_size = Self::size_required_tree(entries);
+        if buffer.len() < total_size {
+            return Err(Error::BufferTooSmall);
+        }
+        let used = Self::construct_tree_into(&mut buffer, entries)?;
+        Ok(used)
+    }
+
+    /// Construct a hierarchical (directory-aware) ROMFS into the given
+    /// embedded-io byte sink, as a [`FormatVersion::Version400`] image.
+    ///
+    /// Each directory's child entries are laid out depth-first, immediately
+    /// after the directory's own metadata, and the directory's `file_size`
+    /// field is computed for you - it always holds the total size of the
+    /// directory's children, not the value in the supplied
+    /// [`BuildEntry::Directory::metadata`].
+    ///
+    /// Since the header needs to carry a CRC-32 of everything that follows
+    /// it, the entries are written out twice: once into a throwaway sink
+    /// that only feeds a running CRC-32 (to learn the checksum before the
+    /// header is written), then for real.
+    ///
+    /// Tells you how many bytes it wrote to the given buffer.
+    pub fn construct_tree_into<S, T, SINK>(
+        buffer: &mut SINK,
+        entries: &[BuildEntry<S, T>],
+    ) -> Result<usize, Error>
+    where
+        S: AsRef<str>,
+        T: AsRef<[u8]>,
+        SINK: embedded_io::Write,
+    {
+        let total_size = Self::size_required_tree(entries);
+
+        let mut crc_sink = Crc32Sink(crc32::Crc32::new());
+        Self::write_tree(&mut crc_sink, entries)?;
+        let image_crc = crc_sink.0.finish();
+
+        let file_header = Header {
+            format_version: FormatVersion::Version400,
+            total_size: total_size as u32,
+            image_crc: Some(image_crc),
+        };
+        let mut used = file_header.write_into(buffer)?;
+        used += Self::write_tree(buffer, entries)?;
+
+        assert_eq!(used, total_size);
+
+        Ok(total_size)
+    }
+
+    /// Write out one directory level's worth of entries, depth-first.
+    fn write_tree<S, T, SINK>(buffer: &mut SINK, entries: &[BuildEntry<S, T>]) -> Result<usize, Error>
+    where
+        S: AsRef<str>,
+        T: AsRef<[u8]>,
+        SINK: embedded_io::Write,
+    {
+        let mut used = 0;
+        for entry in entries {
+            match entry {
+                BuildEntry::File { metadata, contents } => {
+                    let contents: &[u8] = contents.as_ref();
+                    used += metadata.write_into_with_kind(
+                        buffer,
+                        EntryKind::File,
+                        contents.len() as u32,
+                        metadata.content_crc,
+                    )?;
+                    buffer.write_all(contents).map_err(|_| Error::SinkError)?;
+                    used += contents.len();
+                }
+                BuildEntry::Directory { metadata, children } => {
+                    let children_size = Self::size_required_tree_entries(children);
+                    used += metadata.write_into_with_kind(
+                        buffer,
+                        EntryKind::Directory,
+                        children_size as u32,
+                        metadata.content_crc,
+                    )?;
+                    used += Self::write_tree(buffer, children)?;
+                }
+            }
+        }
+        Ok(used)
+    }
+
+    /// Tells you how many bytes you need to make a hierarchical ROMFS from
+    /// this tree of entries.
+    pub fn size_required_tree<S, T>(entries: &[BuildEntry<S, T>]) -> usize
+    where
+        S: AsRef<str>,
+        T: AsRef<[u8]>,
+    {
+        Header::size_for(FormatVersion::Version400) + Self::size_required_tree_entries(entries)
+    }
+
+    /// Sums the on-disk size of one directory level's worth of entries,
+    /// recursing into any sub-directories.
+    fn size_required_tree_entries<S, T>(entries: &[BuildEntry<S, T>]) -> usize
+    where
+        S: AsRef<str>,
+        T: AsRef<[u8]>,
+    {
+        let mut total_size = 0;
+        for entry in entries {
+            // one byte for the file/directory tag, one for the compression
+            // tag, four for the uncompressed size, and four for the
+            // content CRC-32
+            total_size += 10 + EntryMetadata::<S>::SIZE;
+            let metadata = match entry {
+                BuildEntry::File { metadata, .. } => metadata,
+                BuildEntry::Directory { metadata, .. } => metadata,
+            };
+            let file_name_len = metadata.file_name.as_ref().len();
+            if file_name_len > EntryMetadata::<S>::FILENAME_SIZE {
+                // a 2-byte length plus the full name, for entries whose
+                // name doesn't fit the short field - see
+                // EntryMetadata::LONG_NAME_FLAG
+                total_size += 2 + file_name_len;
+            }
+            match entry {
+                BuildEntry::File { contents, .. } => {
+                    total_size += contents.as_ref().len();
+                }
+                BuildEntry::Directory { children, .. } => {
+                    total_size += Self::size_required_tree_entries(children);
+                }
+            }
+        }
+        total_size
+    }
+}
+
+/// A write sink that discards its input but folds it into a running
+/// CRC-32, used by [`RomFs::construct_tree_into`] to learn a whole image's
+/// checksum with a throwaway pass before writing the real header (which
+/// needs to carry that checksum).
+struct Crc32Sink(crc32::Crc32);
+
+impl embedded_io::ErrorType for Crc32Sink {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_io::Write for Crc32Sink {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Forwards every write through to a real sink while also folding the same
+/// bytes into a running CRC-32, so a whole-image checksum can be
+/// accumulated incrementally as the image streams out, without a second
+/// pass over the data.
+struct Crc32Tee<'a, SINK> {
+    sink: &'a mut SINK,
+    crc: &'a mut crc32::Crc32,
+}
+
+impl<'a, SINK> embedded_io::ErrorType for Crc32Tee<'a, SINK>
+where
+    SINK: embedded_io::Write,
+{
+    type Error = SINK::Error;
+}
+
+impl<'a, SINK> embedded_io::Write for Crc32Tee<'a, SINK>
+where
+    SINK: embedded_io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let n = self.sink.write(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.sink.flush()
+    }
+}
+
+/// An incremental, append-as-you-go builder for streaming a ROMFS image
+/// straight onto an embedded-io sink, without collecting every entry into
+/// memory (or even knowing the full set of entries) up front.
+///
+/// Mirrors the `tar` crate's `Builder`: construct one with [`Self::new`],
+/// call [`Self::append`] once per file, then call [`Self::finish`] to patch
+/// the header with the final image size and CRC-32. Unlike
+/// [`RomFs::construct_tree`], there's no way to append a directory - images
+/// built this way are flat, like [`RomFs::construct`].
+pub struct RomFsBuilder<SINK>
+where
+    SINK: embedded_io::Write + embedded_io::Seek,
+{
+    sink: SINK,
+    total_size: u32,
+    crc: crc32::Crc32,
+}
+
+impl<SINK> RomFsBuilder<SINK>
+where
+    SINK: embedded_io::Write + embedded_io::Seek,
+{
+    /// Start a new image, writing a placeholder [`FormatVersion::Version400`]
+    /// header to `sink`.
+    ///
+    /// The header's `total_size` and `image_crc` are corrected once
+    /// [`Self::finish`] is called.
+    pub fn new(mut sink: SINK) -> Result<Self, Error> {
+        let header = Header {
+            format_version: FormatVersion::Version400,
+            total_size: 0,
+            image_crc: Some(0),
+        };
+        let total_size = header.write_into(&mut sink)? as u32;
+        Ok(RomFsBuilder {
+            sink,
+            total_size,
+            crc: crc32::Crc32::new(),
+        })
+    }
+
+    /// Append one file's metadata and contents to the image.
+    ///
+    /// `metadata.file_size` must already hold the number of bytes `contents`
+    /// will yield - the usual sequence is to measure (or compress) the file
+    /// first and set `file_size` to match, then call this.
+    /// `metadata.content_crc` is ignored - `contents` is read through once
+    /// up front to compute the real CRC-32 of the bytes about to be written
+    /// (seeking back to where it started afterwards), so [`Entry::verify`]
+    /// always has something trustworthy to check against. Exactly
+    /// `file_size` bytes are then read from `contents` a second time and
+    /// streamed straight to the sink, in bounded-size chunks, so the whole
+    /// file never needs to be held in memory at once.
+    pub fn append<S, R>(
+        &mut self,
+        metadata: &EntryMetadata<S>,
+        contents: &mut R,
+    ) -> Result<(), Error>
+    where
+        S: AsRef<str>,
+        R: embedded_io::Read + embedded_io::Seek,
+    {
+        let content_start = contents
+            .seek(embedded_io::SeekFrom::Current(0))
+            .map_err(|_| Error::SourceError)?;
+
+        let mut buf = [0u8; 256];
+        let mut remaining = metadata.file_size as usize;
+        let mut entry_crc = crc32::Crc32::new();
+        while remaining > 0 {
+            let want = core::cmp::min(buf.len(), remaining);
+            let n = contents
+                .read(&mut buf[..want])
+                .map_err(|_| Error::SourceError)?;
+            if n == 0 {
+                return Err(Error::SourceError);
+            }
+            entry_crc.update(&buf[..n]);
+            remaining -= n;
+        }
+        let content_crc = entry_crc.finish();
+        contents
+            .seek(embedded_io::SeekFrom::Start(content_start))
+            .map_err(|_| Error::SourceError)?;
+
+        let mut tee = Crc32Tee {
+            sink: &mut self.sink,
+            crc: &mut self.crc,
+        };
+        self.total_size += metadata.write_into_with_kind(
+            &mut tee,
+            EntryKind::File,
+            metadata.file_size,
+            content_crc,
+        )? as u32;
+
+        let mut remaining = metadata.file_size as usize;
+        while remaining > 0 {
+            let want = core::cmp::min(buf.len(), remaining);
+            let n = contents
+                .read(&mut buf[..want])
+                .map_err(|_| Error::SourceError)?;
+            if n == 0 {
+                return Err(Error::SourceError);
+            }
+            let mut tee = Crc32Tee {
+                sink: &mut self.sink,
+                crc: &mut self.crc,
+            };
+            tee.write_all(&buf[..n]).map_err(|_| Error::SinkError)?;
+            remaining -= n;
+        }
+        self.total_size += metadata.file_size;
+
+        Ok(())
+    }
+
+    /// Finish the image: seek back and patch the header's `total_size` and
+    /// `image_crc` fields with their final values, then hand back the
+    /// underlying sink.
+    ///
+    /// The sink is left seeked to wherever the last patch write landed (just
+    /// past [`Header::IMAGE_CRC_OFFSET`]), not rewound to the start - seek
+    /// back to `0` yourself before handing it to something that expects to
+    /// read the image from the beginning, such as [`StreamRomFs::from_reader`].
+    pub fn finish(mut self) -> Result<SINK, Error> {
+        let image_crc = self.crc.finish();
+        self.sink
+            .seek(embedded_io::SeekFrom::Start(
+                Header::TOTAL_SIZE_OFFSET as u64,
+            ))
+            .map_err(|_| Error::SinkError)?;
+        self.sink
+            .write_all(&self.total_size.to_be_bytes())
+            .map_err(|_| Error::SinkError)?;
+        self.sink
+            .seek(embedded_io::SeekFrom::Start(
+                Header::IMAGE_CRC_OFFSET as u64,
+            ))
+            .map_err(|_| Error::SinkError)?;
+        self.sink
+            .write_all(&image_crc.to_be_bytes())
+            .map_err(|_| Error::SinkError)?;
+        Ok(self.sink)
+    }
 }
 
 impl<'a> IntoIterator for RomFs<'a> {
-    type Item = Result<Entry<&'a str, &'a [u8]>, Error>;
+    type Item = Result<DirEntry<'a>, Error>;
 
     type IntoIter = RomFsEntryIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
         RomFsEntryIter {
             contents: self.contents,
+            format_version: self.format_version,
         }
     }
 }
 
 impl<'a> IntoIterator for &'a RomFs<'a> {
-    type Item = Result<Entry<&'a str, &'a [u8]>, Error>;
+    type Item = Result<DirEntry<'a>, Error>;
 
     type IntoIter = RomFsEntryIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
         RomFsEntryIter {
             contents: self.contents,
+            format_version: self.format_version,
         }
     }
 }
 
-/// An iterator for working through the entries in a ROMFS
+/// An iterator for working through the entries in one directory level of a
+/// ROMFS (the root level, for [`FormatVersion::Version100`] images, which
+/// have no sub-directories).
 pub struct RomFsEntryIter<'a> {
     contents: &'a [u8],
+    format_version: FormatVersion,
 }
 
 impl<'a> Iterator for RomFsEntryIter<'a> {
-    type Item = Result<Entry<&'a str, &'a [u8]>, Error>;
+    type Item = Result<DirEntry<'a>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.contents.is_empty() {
             return None;
         }
-        match EntryMetadata::<&str>::from_bytes(self.contents) {
-            Ok((hdr, remainder)) => {
-                if hdr.file_size as usize > remainder.len() {
+        let parsed = match self.format_version {
+            FormatVersion::Version100 => EntryMetadata::<&str>::from_bytes(self.contents)
+                .map(|(metadata, remainder)| (EntryKind::File, metadata, remainder)),
+            FormatVersion::Version200 => {
+                EntryMetadata::<&str>::from_bytes_with_kind(self.contents)
+            }
+            FormatVersion::Version300 => {
+                EntryMetadata::<&str>::from_bytes_with_compression(self.contents)
+            }
+            FormatVersion::Version400 => {
+                EntryMetadata::<&str>::from_bytes_with_checksum(self.contents)
+            }
+        };
+        match parsed {
+            Ok((kind, metadata, remainder)) => {
+                if metadata.file_size as usize > remainder.len() {
                     // stop if we run out of data
                     return None;
                 }
-                let (contents, remainder) = remainder.split_at(hdr.file_size as usize);
+                let (region, remainder) = remainder.split_at(metadata.file_size as usize);
                 self.contents = remainder;
-                Some(Ok(Entry {
-                    metadata: hdr,
-                    contents,
+                Some(Ok(match kind {
+                    EntryKind::File => DirEntry::File(Entry {
+                        metadata,
+                        contents: region,
+                    }),
+                    EntryKind::Directory => DirEntry::Directory {
+                        metadata,
+                        children: region,
+                    },
                 }))
             }
             Err(e) => {
@@ -199,23 +724,380 @@ impl<'a> Iterator for RomFsEntryIter<'a> {
     }
 }
 
+/// An iterator for working through the entries in one directory of a
+/// [`FormatVersion::Version200`] image, as returned by [`RomFs::open_dir`].
+pub struct DirIter<'a>(RomFsEntryIter<'a>);
+
+impl<'a> Iterator for DirIter<'a> {
+    type Item = Result<DirEntry<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// The kind of thing an on-disk entry represents.
+///
+/// Only present in [`FormatVersion::Version200`] (and later) images - a
+/// [`FormatVersion::Version100`] image has no directories, so every entry
+/// is implicitly [`EntryKind::File`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EntryKind {
+    /// A regular file, whose contents are bytes.
+    File = 0,
+    /// A directory, whose contents are a nested run of entries.
+    Directory = 1,
+}
+
+impl TryFrom<u8> for EntryKind {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EntryKind::File),
+            1 => Ok(EntryKind::Directory),
+            _ => Err(Error::UnknownVersion),
+        }
+    }
+}
+
+/// One entry found while iterating a directory level of the ROMFS.
+///
+/// See [`RomFs::open_dir`] and [`RomFsEntryIter`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum DirEntry<'a> {
+    /// A regular file and its contents.
+    File(Entry<&'a str, &'a [u8]>),
+    /// A directory. `children` holds the raw, not-yet-parsed bytes of the
+    /// directory's own entries - iterate them with [`RomFsEntryIter`].
+    Directory {
+        /// Metadata for the directory itself.
+        metadata: EntryMetadata<&'a str>,
+        /// The raw bytes of this directory's children.
+        children: &'a [u8],
+    },
+}
+
+/// An owned file name, as produced by the streaming front end.
+///
+/// We can't borrow a file name out of a reader the way we can out of a byte
+/// slice, so instead we copy it into a fixed-capacity string sized to match
+/// [`EntryMetadata::SIZE`]'s short-name field.
+pub type FileName = heapless::String<{ EntryMetadata::<&'static str>::FILENAME_SIZE }>;
+
+/// Mounts a ROMFS from an [`embedded_io::Read`] + [`embedded_io::Seek`]
+/// source, such as a SPI flash driver, rather than requiring the whole
+/// image to be read into RAM up front.
+///
+/// Only entry metadata is read eagerly, one entry at a time, as you iterate
+/// - file contents are left on the source until you call
+/// [`StreamEntry::read_contents`].
+pub struct StreamRomFs<R> {
+    reader: R,
+    format_version: FormatVersion,
+    /// Absolute offset, from the start of the reader, of the first entry.
+    entries_start: u32,
+    /// Absolute offset, from the start of the reader, one past the last
+    /// entry.
+    entries_end: u32,
+}
+
+impl<R> StreamRomFs<R>
+where
+    R: embedded_io::Read + embedded_io::Seek,
+{
+    /// Mount a ROMFS from a streaming reader, by parsing its header.
+    ///
+    /// The header is at least [`Header::FIXED_SIZE`] bytes - a further 4
+    /// bytes are read if it turns out to be a [`FormatVersion::Version400`]
+    /// image, since only then does the header carry a CRC-32.
+    pub fn from_reader(mut reader: R) -> Result<StreamRomFs<R>, Error> {
+        let mut header_bytes = [0u8; Header::FIXED_SIZE + 4];
+        reader
+            .read_exact(&mut header_bytes[..Header::FIXED_SIZE])
+            .map_err(|_| Error::SourceError)?;
+        let format_version = Header::peek_version(&header_bytes[..Header::FIXED_SIZE])?;
+        let header_size = Header::size_for(format_version);
+        if header_size > Header::FIXED_SIZE {
+            reader
+                .read_exact(&mut header_bytes[Header::FIXED_SIZE..header_size])
+                .map_err(|_| Error::SourceError)?;
+        }
+        let (header, _) = Header::from_bytes(&header_bytes[..header_size])?;
+        Ok(StreamRomFs {
+            reader,
+            format_version: header.format_version,
+            entries_start: header_size as u32,
+            entries_end: header.total_size,
+        })
+    }
+
+    /// Iterate over the entries at the root of this ROMFS.
+    ///
+    /// The returned iterator borrows the reader, seeking it to each entry
+    /// in turn - file contents are not read until requested.
+    pub fn entries(&mut self) -> StreamEntryIter<'_, R> {
+        StreamEntryIter {
+            reader: &mut self.reader,
+            next_offset: self.entries_start,
+            end_offset: self.entries_end,
+            format_version: self.format_version,
+        }
+    }
+}
+
+/// An iterator that reads ROMFS entry metadata, one entry at a time, from
+/// an [`embedded_io::Read`] + [`embedded_io::Seek`] source.
+pub struct StreamEntryIter<'r, R> {
+    reader: &'r mut R,
+    next_offset: u32,
+    end_offset: u32,
+    format_version: FormatVersion,
+}
+
+impl<'r, R> Iterator for StreamEntryIter<'r, R>
+where
+    R: embedded_io::Read + embedded_io::Seek,
+{
+    type Item = Result<StreamEntry<FileName>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_offset >= self.end_offset {
+            return None;
+        }
+        match self.read_one() {
+            Ok(entry) => Some(Ok(entry)),
+            Err(e) => {
+                // stop the iteration
+                self.next_offset = self.end_offset;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'r, R> StreamEntryIter<'r, R>
+where
+    R: embedded_io::Read + embedded_io::Seek,
+{
+    fn read_one(&mut self) -> Result<StreamEntry<FileName>, Error> {
+        self.reader
+            .seek(embedded_io::SeekFrom::Start(self.next_offset as u64))
+            .map_err(|_| Error::SourceError)?;
+
+        let mut kind = EntryKind::File;
+        let mut compression = Compression::None;
+        let mut uncompressed_size = None;
+        let mut content_crc = None;
+        let mut long_name = false;
+
+        if self.format_version != FormatVersion::Version100 {
+            let mut tag = [0u8; 1];
+            self.reader
+                .read_exact(&mut tag)
+                .map_err(|_| Error::SourceError)?;
+            long_name = tag[0] & EntryMetadata::<&str>::LONG_NAME_FLAG != 0;
+            kind = EntryKind::try_from(tag[0] & !EntryMetadata::<&str>::LONG_NAME_FLAG)?;
+        }
+        if self.format_version == FormatVersion::Version300
+            || self.format_version == FormatVersion::Version400
+        {
+            let mut tag = [0u8; 1];
+            self.reader
+                .read_exact(&mut tag)
+                .map_err(|_| Error::SourceError)?;
+            compression = Compression::try_from(tag[0])?;
+            let mut size_bytes = [0u8; 4];
+            self.reader
+                .read_exact(&mut size_bytes)
+                .map_err(|_| Error::SourceError)?;
+            uncompressed_size = Some(u32::from_be_bytes(size_bytes));
+        }
+        if self.format_version == FormatVersion::Version400 {
+            let mut crc_bytes = [0u8; 4];
+            self.reader
+                .read_exact(&mut crc_bytes)
+                .map_err(|_| Error::SourceError)?;
+            content_crc = Some(u32::from_be_bytes(crc_bytes));
+        }
+
+        let mut fields = [0u8; EntryMetadata::<&str>::SIZE];
+        self.reader
+            .read_exact(&mut fields)
+            .map_err(|_| Error::SourceError)?;
+        let (borrowed, _) = EntryMetadata::<&str>::from_fields(&fields)?;
+        let mut file_name = FileName::new();
+        file_name
+            .push_str(borrowed.file_name)
+            .map_err(|_| Error::FilenameTooLong)?;
+        let metadata = EntryMetadata {
+            file_name,
+            ctime: borrowed.ctime,
+            file_size: borrowed.file_size,
+            compression,
+            uncompressed_size: uncompressed_size.unwrap_or(borrowed.file_size),
+            content_crc: content_crc.unwrap_or(0),
+        };
+
+        // a long-filename extension record, if present, is skipped rather
+        // than read: `FileName` only has room for the short name, so this
+        // reader always reports that (unlike the slice-based reader, which
+        // reports the full name). It still needs to step past the extra bytes to
+        // find the entry's actual contents, though.
+        let mut long_name_size = 0;
+        if long_name {
+            let mut len_bytes = [0u8; 2];
+            self.reader
+                .read_exact(&mut len_bytes)
+                .map_err(|_| Error::SourceError)?;
+            let name_len = u16::from_be_bytes(len_bytes) as i64;
+            self.reader
+                .seek(embedded_io::SeekFrom::Current(name_len))
+                .map_err(|_| Error::SourceError)?;
+            long_name_size = 2 + name_len as u32;
+        }
+
+        let tag_size = match self.format_version {
+            FormatVersion::Version100 => 0,
+            FormatVersion::Version200 => 1,
+            FormatVersion::Version300 => 6,
+            FormatVersion::Version400 => 10,
+        };
+        let content_offset =
+            self.next_offset + tag_size + EntryMetadata::<&str>::SIZE as u32 + long_name_size;
+        let content_len = metadata.file_size;
+        self.next_offset = content_offset + content_len;
+
+        Ok(match kind {
+            EntryKind::File => StreamEntry::File {
+                metadata,
+                content_offset,
+                content_len,
+            },
+            EntryKind::Directory => StreamEntry::Directory {
+                metadata,
+                children_offset: content_offset,
+                children_len: content_len,
+            },
+        })
+    }
+}
+
+/// One entry found while iterating a [`StreamRomFs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEntry<S>
+where
+    S: AsRef<str>,
+{
+    /// A regular file. Use [`StreamEntry::read_contents`] to read its data.
+    File {
+        /// Metadata for this entry.
+        metadata: EntryMetadata<S>,
+        /// Absolute offset, from the start of the reader, of this file's
+        /// contents.
+        content_offset: u32,
+        /// The length, in bytes, of this file's contents.
+        content_len: u32,
+    },
+    /// A directory.
+    Directory {
+        /// Metadata for the directory itself.
+        metadata: EntryMetadata<S>,
+        /// Absolute offset, from the start of the reader, of this
+        /// directory's children.
+        children_offset: u32,
+        /// The total length, in bytes, of this directory's children.
+        children_len: u32,
+    },
+}
+
+impl<S> StreamEntry<S>
+where
+    S: AsRef<str>,
+{
+    /// Read this file's contents from the given reader, into `buf`.
+    ///
+    /// Seeks the reader to the file's stored content offset, then fills
+    /// `buf` in chunks (looping over short reads) until either `buf` is
+    /// full or the file's recorded length has been reached.
+    ///
+    /// Returns the number of bytes read. Returns `0` (without reading)
+    /// if called on a [`StreamEntry::Directory`].
+    pub fn read_contents<R>(&self, reader: &mut R, buf: &mut [u8]) -> Result<usize, Error>
+    where
+        R: embedded_io::Read + embedded_io::Seek,
+    {
+        let Self::File {
+            content_offset,
+            content_len,
+            ..
+        } = self
+        else {
+            return Ok(0);
+        };
+        reader
+            .seek(embedded_io::SeekFrom::Start(*content_offset as u64))
+            .map_err(|_| Error::SourceError)?;
+        let want = core::cmp::min(buf.len(), *content_len as usize);
+        let mut have = 0;
+        while have < want {
+            let n = reader
+                .read(&mut buf[have..want])
+                .map_err(|_| Error::SourceError)?;
+            if n == 0 {
+                break;
+            }
+            have += n;
+        }
+        Ok(have)
+    }
+}
+
 /// Found at the start of the ROMFS image
 ///
 /// In flash we have 8 bytes of magic number, four bytes of version and four
-/// bytes of length.
+/// bytes of length, plus (from [`FormatVersion::Version400`] onwards) four
+/// bytes of whole-image CRC-32.
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Header {
     pub format_version: FormatVersion,
     pub total_size: u32,
+    /// The CRC-32 of everything after the header. Only present from
+    /// [`FormatVersion::Version400`] onwards.
+    pub image_crc: Option<u32>,
 }
 
 impl Header {
     const MAGIC_VALUE: [u8; 8] = *b"NeoROMFS";
     const FORMAT_V100: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+    const FORMAT_V200: [u8; 4] = [0x00, 0x02, 0x00, 0x00];
+    const FORMAT_V300: [u8; 4] = [0x00, 0x03, 0x00, 0x00];
+    const FORMAT_V400: [u8; 4] = [0x00, 0x04, 0x00, 0x00];
     const FIXED_SIZE: usize = 8 + 4 + 4;
+    /// Byte offset of the `total_size` field, for patching in place once
+    /// the final size is known (see [`RomFsBuilder::finish`]).
+    const TOTAL_SIZE_OFFSET: usize = 8 + 4;
+    /// Byte offset of the `image_crc` field, for patching in place once the
+    /// final CRC is known (see [`RomFsBuilder::finish`]). Only meaningful
+    /// for [`FormatVersion::Version400`] headers.
+    const IMAGE_CRC_OFFSET: usize = Self::FIXED_SIZE;
 
-    /// Parse a header from raw bytes.
-    fn from_bytes(data: &[u8]) -> Result<(Header, &[u8]), Error> {
+    /// The on-disk size of a header of the given format version.
+    fn size_for(format_version: FormatVersion) -> usize {
+        match format_version {
+            FormatVersion::Version400 => Self::FIXED_SIZE + 4,
+            _ => Self::FIXED_SIZE,
+        }
+    }
+
+    /// Peek at the magic number and version tag at the start of `data`,
+    /// without requiring the rest of the header (whose size depends on the
+    /// version) to be present yet.
+    ///
+    /// Used by [`StreamRomFs::from_reader`], which must learn the version
+    /// before it knows how many more header bytes to read.
+    fn peek_version(data: &[u8]) -> Result<FormatVersion, Error> {
         let Some(magic_value) = data.get(0..8) else {
             return Err(Error::BufferTooSmall);
         };
@@ -226,21 +1108,43 @@ impl Header {
             return Err(Error::BufferTooSmall);
         };
         if format_version == Self::FORMAT_V100 {
-            let Some(total_size) = data.get(12..16) else {
-                return Err(Error::UnknownVersion);
-            };
-            let total_size: [u8; 4] = total_size.try_into().unwrap();
-            let total_size = u32::from_be_bytes(total_size);
-            let hdr = Header {
-                format_version: FormatVersion::Version100,
-                total_size,
-            };
-            Ok((hdr, &data[16..]))
+            Ok(FormatVersion::Version100)
+        } else if format_version == Self::FORMAT_V200 {
+            Ok(FormatVersion::Version200)
+        } else if format_version == Self::FORMAT_V300 {
+            Ok(FormatVersion::Version300)
+        } else if format_version == Self::FORMAT_V400 {
+            Ok(FormatVersion::Version400)
         } else {
             Err(Error::UnknownVersion)
         }
     }
 
+    /// Parse a header from raw bytes.
+    fn from_bytes(data: &[u8]) -> Result<(Header, &[u8]), Error> {
+        let format_version = Self::peek_version(data)?;
+        let Some(total_size) = data.get(12..16) else {
+            return Err(Error::UnknownVersion);
+        };
+        let total_size: [u8; 4] = total_size.try_into().unwrap();
+        let total_size = u32::from_be_bytes(total_size);
+        let image_crc = if format_version == FormatVersion::Version400 {
+            let Some(image_crc) = data.get(16..20) else {
+                return Err(Error::BufferTooSmall);
+            };
+            let image_crc: [u8; 4] = image_crc.try_into().unwrap();
+            Some(u32::from_be_bytes(image_crc))
+        } else {
+            None
+        };
+        let hdr = Header {
+            format_version,
+            total_size,
+            image_crc,
+        };
+        Ok((hdr, &data[Self::size_for(format_version)..]))
+    }
+
     /// Write the header to the given buffer
     fn write_into<SINK>(&self, buffer: &mut SINK) -> Result<usize, Error>
     where
@@ -252,13 +1156,22 @@ impl Header {
         buffer
             .write_all(match self.format_version {
                 FormatVersion::Version100 => &Self::FORMAT_V100,
+                FormatVersion::Version200 => &Self::FORMAT_V200,
+                FormatVersion::Version300 => &Self::FORMAT_V300,
+                FormatVersion::Version400 => &Self::FORMAT_V400,
             })
             .map_err(|_| Error::SinkError)?;
         let size_bytes = self.total_size.to_be_bytes();
         buffer
             .write_all(&size_bytes)
             .map_err(|_| Error::SinkError)?;
-        Ok(Header::FIXED_SIZE)
+        if self.format_version == FormatVersion::Version400 {
+            let image_crc = self.image_crc.unwrap_or(0).to_be_bytes();
+            buffer
+                .write_all(&image_crc)
+                .map_err(|_| Error::SinkError)?;
+        }
+        Ok(Self::size_for(self.format_version))
     }
 }
 
@@ -275,12 +1188,101 @@ where
     ///
     /// Call `contents.as_ref()` to get the contents as a byte slice
     /// (`&[u8]`).
+    ///
+    /// If `metadata.compression` is not [`Compression::None`], these are
+    /// the *compressed* bytes - pass them through [`Entry::decompress_into`]
+    /// to recover the original contents.
     pub contents: T,
 }
 
+impl<S, T> Entry<S, T>
+where
+    S: AsRef<str>,
+    T: AsRef<[u8]>,
+{
+    /// Decompress this entry's contents into `out`, returning the number of
+    /// bytes written.
+    ///
+    /// If `metadata.compression` is [`Compression::None`] this is just a
+    /// copy. `out` must be at least `metadata.uncompressed_size` bytes.
+    pub fn decompress_into(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let contents = self.contents.as_ref();
+        match self.metadata.compression {
+            Compression::None => {
+                if out.len() < contents.len() {
+                    return Err(Error::BufferTooSmall);
+                }
+                out[..contents.len()].copy_from_slice(contents);
+                Ok(contents.len())
+            }
+            Compression::Lzss => {
+                let written = lzss::decompress_into(contents, out)?;
+                if written != self.metadata.uncompressed_size as usize {
+                    return Err(Error::DecompressError);
+                }
+                Ok(written)
+            }
+        }
+    }
+
+    /// Check this entry's stored contents against its CRC-32.
+    ///
+    /// Compares a freshly computed CRC-32 of `self.contents` (i.e. the
+    /// bytes as stored on disk, before any decompression) against
+    /// [`EntryMetadata::content_crc`]. Entries read from an image older
+    /// than [`FormatVersion::Version400`] don't carry a per-entry
+    /// checksum - `content_crc` is always `0` for those, and a genuine
+    /// file is vanishingly unlikely to CRC to `0`, so this will almost
+    /// always return `false` for them.
+    pub fn verify(&self) -> bool {
+        crc32::crc32(self.contents.as_ref()) == self.metadata.content_crc
+    }
+}
+
+/// A node to be written into a [`FormatVersion::Version400`] image by
+/// [`RomFs::construct_tree`].
+///
+/// Unlike [`Entry`], a `BuildEntry` can be a directory, whose `children`
+/// are themselves a slice of `BuildEntry` - this lets a whole tree be laid
+/// out using only borrowed slices, with no heap allocation required.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuildEntry<'e, S, T>
+where
+    S: AsRef<str>,
+    T: AsRef<[u8]>,
+{
+    /// A regular file, with its contents.
+    ///
+    /// `metadata.compression` is written verbatim, and so is
+    /// `metadata.uncompressed_size` unless the compression is
+    /// [`Compression::None`] (then the length of `contents` is written in
+    /// its place). To store a compressed file, compress `contents` first
+    /// (e.g. with the `lzss` module) and set those fields to match.
+    File {
+        /// Metadata for this entry.
+        metadata: EntryMetadata<S>,
+        /// The file data for this entry.
+        contents: T,
+    },
+    /// A directory, containing zero or more child entries.
+    Directory {
+        /// Metadata for the directory itself. The `file_size` field is
+        /// ignored - it is recomputed from `children` when the image is
+        /// constructed.
+        metadata: EntryMetadata<S>,
+        /// The entries contained within this directory.
+        children: &'e [BuildEntry<'e, S, T>],
+    },
+}
+
 /// Metadata for an entry in the ROMFS.
 ///
-/// Occupies [`Self::SIZE`] bytes of ROM when encoded.
+/// Occupies [`Self::SIZE`] bytes of ROM when encoded (plus one further byte,
+/// for the file/directory tag, in [`FormatVersion::Version200`] images; plus
+/// six further bytes, for the file/directory tag, compression tag and
+/// uncompressed size, in [`FormatVersion::Version300`] images; plus a
+/// further four bytes, for a content CRC-32, in
+/// [`FormatVersion::Version400`] images).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EntryMetadata<S>
 where
@@ -294,13 +1296,52 @@ where
     /// The creation time, of the file associated with this entry.
     pub ctime: neotron_api::file::Time,
     /// The size, in bytes, of the file associated with this entry.
+    ///
+    /// For a directory entry in a [`FormatVersion::Version200`] image, this
+    /// is the total size of the directory's children, not a "file size" in
+    /// the usual sense.
+    ///
+    /// For a compressed entry in a [`FormatVersion::Version300`] image, this
+    /// is the compressed (on-disk) size - see [`Self::uncompressed_size`]
+    /// for the size once decompressed.
     pub file_size: u32,
+    /// The compression scheme used to store this entry's contents.
+    ///
+    /// Always [`Compression::None`] for entries read from a
+    /// [`FormatVersion::Version100`] or [`FormatVersion::Version200`] image,
+    /// as those formats predate compression support.
+    pub compression: Compression,
+    /// The size, in bytes, of the entry's contents once decompressed.
+    ///
+    /// Equal to [`Self::file_size`] unless [`Self::compression`] is
+    /// something other than [`Compression::None`].
+    pub uncompressed_size: u32,
+    /// The CRC-32 of this entry's stored contents (i.e. of
+    /// [`Entry::contents`], before any decompression), for use with
+    /// [`Entry::verify`].
+    ///
+    /// Always `0` for entries read from an image older than
+    /// [`FormatVersion::Version400`], as those formats predate per-entry
+    /// checksums.
+    pub content_crc: u32,
 }
 
 impl<S> EntryMetadata<S>
 where
     S: AsRef<str>,
 {
+    /// Flag bit within the file/directory tag byte (written by
+    /// [`Self::write_into_with_kind`], so only present from
+    /// [`FormatVersion::Version200`] onwards) that says this entry's fixed
+    /// 14-byte name field holds a truncated *short* name, and the real
+    /// name immediately follows the fixed record as a 2-byte big-endian
+    /// length plus that many bytes of UTF-8.
+    ///
+    /// Mirrors the way ISO 9660's Joliet extension keeps a short 8.3 name
+    /// in the base record while storing the full name alongside it - a
+    /// reader that doesn't know about the extension still sees a valid
+    /// (if truncated) name.
+    const LONG_NAME_FLAG: u8 = 0x80;
     const FILENAME_SIZE: usize = 14;
     const FILENAME_OFFSET: usize = 0;
     const FILESIZE_SIZE: usize = 4;
@@ -319,6 +1360,125 @@ where
     ///
     /// Returns the entry and the remaining unused bytes, or an error.
     fn from_bytes(data: &[u8]) -> Result<(EntryMetadata<&str>, &[u8]), Error> {
+        Self::from_fields(data)
+    }
+
+    /// Parse a [`FormatVersion::Version200`] entry, which is preceded by a
+    /// one-byte file/directory tag.
+    ///
+    /// Returns the tag, the entry, and the remaining unused bytes, or an
+    /// error.
+    fn from_bytes_with_kind(data: &[u8]) -> Result<(EntryKind, EntryMetadata<&str>, &[u8]), Error> {
+        let Some(&kind_byte) = data.first() else {
+            return Err(Error::BufferTooSmall);
+        };
+        let long_name = kind_byte & Self::LONG_NAME_FLAG != 0;
+        let kind = EntryKind::try_from(kind_byte & !Self::LONG_NAME_FLAG)?;
+        let (mut metadata, remainder) = Self::from_fields(&data[1..])?;
+        let remainder = Self::read_long_name(long_name, &mut metadata, remainder)?;
+        Ok((kind, metadata, remainder))
+    }
+
+    /// Parse a [`FormatVersion::Version300`] entry, which is preceded by a
+    /// one-byte file/directory tag, a one-byte compression tag, and a 4-byte
+    /// uncompressed size.
+    ///
+    /// Returns the tag, the entry, and the remaining unused bytes, or an
+    /// error.
+    fn from_bytes_with_compression(
+        data: &[u8],
+    ) -> Result<(EntryKind, EntryMetadata<&str>, &[u8]), Error> {
+        let Some(&kind_byte) = data.first() else {
+            return Err(Error::BufferTooSmall);
+        };
+        let long_name = kind_byte & Self::LONG_NAME_FLAG != 0;
+        let kind = EntryKind::try_from(kind_byte & !Self::LONG_NAME_FLAG)?;
+        let Some(&compression_byte) = data.get(1) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let compression = Compression::try_from(compression_byte)?;
+        let Some(uncompressed_size) = data.get(2..6) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let uncompressed_size: [u8; 4] = uncompressed_size.try_into().unwrap();
+        let uncompressed_size = u32::from_be_bytes(uncompressed_size);
+        let (mut metadata, remainder) = Self::from_fields(&data[6..])?;
+        metadata.compression = compression;
+        metadata.uncompressed_size = uncompressed_size;
+        let remainder = Self::read_long_name(long_name, &mut metadata, remainder)?;
+        Ok((kind, metadata, remainder))
+    }
+
+    /// Parse a [`FormatVersion::Version400`] entry, which is preceded by a
+    /// one-byte file/directory tag, a one-byte compression tag, a 4-byte
+    /// uncompressed size, and a 4-byte content CRC-32.
+    ///
+    /// Returns the tag, the entry, and the remaining unused bytes, or an
+    /// error.
+    fn from_bytes_with_checksum(
+        data: &[u8],
+    ) -> Result<(EntryKind, EntryMetadata<&str>, &[u8]), Error> {
+        let Some(&kind_byte) = data.first() else {
+            return Err(Error::BufferTooSmall);
+        };
+        let long_name = kind_byte & Self::LONG_NAME_FLAG != 0;
+        let kind = EntryKind::try_from(kind_byte & !Self::LONG_NAME_FLAG)?;
+        let Some(&compression_byte) = data.get(1) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let compression = Compression::try_from(compression_byte)?;
+        let Some(uncompressed_size) = data.get(2..6) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let uncompressed_size: [u8; 4] = uncompressed_size.try_into().unwrap();
+        let uncompressed_size = u32::from_be_bytes(uncompressed_size);
+        let Some(content_crc) = data.get(6..10) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let content_crc: [u8; 4] = content_crc.try_into().unwrap();
+        let content_crc = u32::from_be_bytes(content_crc);
+        let (mut metadata, remainder) = Self::from_fields(&data[10..])?;
+        metadata.compression = compression;
+        metadata.uncompressed_size = uncompressed_size;
+        metadata.content_crc = content_crc;
+        let remainder = Self::read_long_name(long_name, &mut metadata, remainder)?;
+        Ok((kind, metadata, remainder))
+    }
+
+    /// If `long_name` is set, read a long-filename extension record (a
+    /// 2-byte big-endian length plus that many bytes of UTF-8) from the
+    /// front of `remainder`, and use it as `metadata.file_name` in place of
+    /// the short name [`Self::from_fields`] already parsed. See
+    /// [`Self::LONG_NAME_FLAG`].
+    ///
+    /// Returns whatever of `remainder` is left after the extension record
+    /// (or `remainder` unchanged, if `long_name` is `false`).
+    fn read_long_name<'d>(
+        long_name: bool,
+        metadata: &mut EntryMetadata<&'d str>,
+        remainder: &'d [u8],
+    ) -> Result<&'d [u8], Error> {
+        if !long_name {
+            return Ok(remainder);
+        }
+        let Some(len_bytes) = remainder.get(0..2) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let len_bytes: [u8; 2] = len_bytes.try_into().unwrap();
+        let name_len = u16::from_be_bytes(len_bytes) as usize;
+        let Some(name_bytes) = remainder.get(2..2 + name_len) else {
+            return Err(Error::BufferTooSmall);
+        };
+        let Ok(file_name) = core::str::from_utf8(name_bytes) else {
+            return Err(Error::NonUnicodeFilename);
+        };
+        metadata.file_name = file_name;
+        Ok(&remainder[2 + name_len..])
+    }
+
+    /// Parse the fixed-size name/size/timestamp fields shared by every
+    /// on-disk representation of an entry.
+    fn from_fields(data: &[u8]) -> Result<(EntryMetadata<&str>, &[u8]), Error> {
         let Some(file_name) =
             data.get(Self::FILENAME_OFFSET..Self::FILENAME_OFFSET + Self::FILENAME_SIZE)
         else {
@@ -360,6 +1520,9 @@ where
             file_name,
             file_size,
             ctime,
+            compression: Compression::None,
+            uncompressed_size: file_size,
+            content_crc: 0,
         };
         Ok((stored_entry, &data[Self::SIZE..]))
     }
@@ -371,20 +1534,95 @@ where
     where
         SINK: embedded_io::Write,
     {
-        // check the file name isn't too long
+        // no tag byte in this format to carry LONG_NAME_FLAG, so a name
+        // that doesn't fit the short field is simply an error here
+        self.write_fields(sink, self.file_size, false)
+    }
+
+    /// Write this entry to the sink, as a [`FormatVersion::Version400`]
+    /// entry preceded by a one-byte file/directory tag, a one-byte
+    /// compression tag, a 4-byte uncompressed size, and a 4-byte content
+    /// CRC-32.
+    ///
+    /// Uses `file_size` in place of `self.file_size` (so directories can
+    /// report the size of their children rather than whatever was passed
+    /// in), and `self.uncompressed_size` unless `self.compression` is
+    /// [`Compression::None`], in which case `file_size` is used for both
+    /// (a directory, or an uncompressed file, has nothing to "uncompress").
+    /// Likewise uses `content_crc` in place of `self.content_crc`, so a
+    /// caller that's computed the real CRC of the bytes it's about to write
+    /// (see [`RomFsBuilder::append`]) isn't stuck with whatever was in
+    /// `self.content_crc` beforehand.
+    ///
+    /// Returns the number of bytes written.
+    fn write_into_with_kind<SINK>(
+        &self,
+        sink: &mut SINK,
+        kind: EntryKind,
+        file_size: u32,
+        content_crc: u32,
+    ) -> Result<usize, Error>
+    where
+        SINK: embedded_io::Write,
+    {
+        let uncompressed_size = match self.compression {
+            Compression::None => file_size,
+            _ => self.uncompressed_size,
+        };
+        let long_name = self.file_name.as_ref().len() > Self::FILENAME_SIZE;
+        let mut kind_byte = kind as u8;
+        if long_name {
+            kind_byte |= Self::LONG_NAME_FLAG;
+        }
+        sink.write_all(&[kind_byte]).map_err(|_| Error::SinkError)?;
+        sink.write_all(&[self.compression as u8])
+            .map_err(|_| Error::SinkError)?;
+        sink.write_all(&uncompressed_size.to_be_bytes())
+            .map_err(|_| Error::SinkError)?;
+        sink.write_all(&content_crc.to_be_bytes())
+            .map_err(|_| Error::SinkError)?;
+        let mut written = 10 + self.write_fields(sink, file_size, true)?;
+        if long_name {
+            written += self.write_long_name(sink)?;
+        }
+        Ok(written)
+    }
+
+    /// Write the fixed-size name/size/timestamp fields shared by every
+    /// on-disk representation of an entry.
+    ///
+    /// If `self.file_name` is longer than [`Self::FILENAME_SIZE`] bytes,
+    /// `allow_long_name` decides what happens: if `true`, a truncated
+    /// short name is written here instead (the caller must then follow up
+    /// with [`Self::write_long_name`] - see [`Self::LONG_NAME_FLAG`]);
+    /// if `false`, it's an error.
+    fn write_fields<SINK>(
+        &self,
+        sink: &mut SINK,
+        file_size: u32,
+        allow_long_name: bool,
+    ) -> Result<usize, Error>
+    where
+        SINK: embedded_io::Write,
+    {
         let file_name = self.file_name.as_ref();
-        let file_name_len = file_name.len();
-        let Some(padding_length) = Self::FILENAME_SIZE.checked_sub(file_name_len) else {
-            return Err(Error::FilenameTooLong);
+        let short_name = if file_name.len() > Self::FILENAME_SIZE {
+            if !allow_long_name {
+                return Err(Error::FilenameTooLong);
+            }
+            Self::truncate_name(file_name)
+        } else {
+            file_name
         };
+        let padding_length = Self::FILENAME_SIZE - short_name.len();
         // copy file name with null padding
-        sink.write_all(file_name.as_bytes())
+        sink.write_all(short_name.as_bytes())
             .map_err(|_| Error::SinkError)?;
         for _ in 0..padding_length {
             sink.write_all(&[0u8]).map_err(|_| Error::SinkError)?;
         }
         // copy file size
-        let file_size = self.file_size.to_be_bytes();
+        let file_size = file_size.to_be_bytes();
         sink.write_all(&file_size).map_err(|_| Error::SinkError)?;
         // copy timestamp
         sink.write_all(&[self.ctime.year_since_1970])
@@ -402,8 +1640,42 @@ where
 
         Ok(Self::SIZE)
     }
+
+    /// Truncate `name` to at most [`Self::FILENAME_SIZE`] bytes, without
+    /// splitting a multi-byte UTF-8 character.
+    fn truncate_name(name: &str) -> &str {
+        let mut end = Self::FILENAME_SIZE.min(name.len());
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        &name[..end]
+    }
+
+    /// Write the long-filename extension record: a 2-byte big-endian
+    /// length followed by that many bytes of `self.file_name`'s UTF-8. See
+    /// [`Self::LONG_NAME_FLAG`].
+    ///
+    /// Returns the number of bytes written.
+    fn write_long_name<SINK>(&self, sink: &mut SINK) -> Result<usize, Error>
+    where
+        SINK: embedded_io::Write,
+    {
+        let file_name = self.file_name.as_ref();
+        let name_len: u16 = file_name
+            .len()
+            .try_into()
+            .map_err(|_| Error::FilenameTooLong)?;
+        sink.write_all(&name_len.to_be_bytes())
+            .map_err(|_| Error::SinkError)?;
+        sink.write_all(file_name.as_bytes())
+            .map_err(|_| Error::SinkError)?;
+        Ok(2 + file_name.len())
+    }
 }
 
+#[cfg(test)]
+extern crate std;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,7 +1759,9 @@ mod tests {
         ];
         let romfs = RomFs::new(&data).unwrap();
         let mut i = romfs.into_iter();
-        let first_item = i.next().unwrap().unwrap();
+        let DirEntry::File(first_item) = i.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
         assert_eq!(first_item.metadata.file_name, "README.TXT");
         assert_eq!(first_item.contents.len(), 4);
         assert_eq!(first_item.contents, &[0x12, 0x34, 0x56, 0x78]);
@@ -534,7 +1808,9 @@ mod tests {
         ];
         let romfs = RomFs::new(&data).unwrap();
         let mut i = romfs.into_iter();
-        let first_item = i.next().unwrap().unwrap();
+        let DirEntry::File(first_item) = i.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
         assert_eq!(first_item.metadata.file_name, "README.TXT");
         assert_eq!(first_item.contents.len(), 4);
         assert_eq!(first_item.contents, &[0x12, 0x34, 0x56, 0x78]);
@@ -549,7 +1825,9 @@ mod tests {
                 seconds: 16
             }
         );
-        let second_item = i.next().unwrap().unwrap();
+        let DirEntry::File(second_item) = i.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
         assert_eq!(second_item.metadata.file_name, "HELLO.DOC");
         assert_eq!(second_item.contents.len(), 3);
         assert_eq!(second_item.contents, &[0xAB, 0xCD, 0xEF]);
@@ -566,6 +1844,284 @@ mod tests {
         );
         assert!(i.next().is_none());
     }
+
+    #[test]
+    fn decode_nested_directory() {
+        // A Version200 image containing one directory ("BIN") holding one
+        // file ("HELLO.ELF").
+        let inner_metadata_size = EntryMetadata::<&str>::SIZE;
+        let inner_entry_size = 1 + inner_metadata_size + 4;
+        let outer_entry_size = 1 + inner_metadata_size + inner_entry_size;
+        let total_size = 16 + outer_entry_size;
+
+        let mut image = std::vec::Vec::new();
+        image.extend_from_slice(b"NeoROMFS");
+        image.extend_from_slice(&[0x00, 0x02, 0x00, 0x00]);
+        image.extend_from_slice(&(total_size as u32).to_be_bytes());
+        // directory tag
+        image.push(EntryKind::Directory as u8);
+        // directory name, padded
+        image.extend_from_slice(b"BIN\0\0\0\0\0\0\0\0\0\0\0");
+        // directory "file size" (size of children)
+        image.extend_from_slice(&(inner_entry_size as u32).to_be_bytes());
+        // directory timestamp
+        image.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        // file tag
+        image.push(EntryKind::File as u8);
+        // file name, padded
+        image.extend_from_slice(b"HELLO.ELF\0\0\0\0\0");
+        // file size
+        image.extend_from_slice(&4u32.to_be_bytes());
+        // file timestamp
+        image.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        // file contents
+        image.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let romfs = RomFs::new(&image).unwrap();
+        let entry = romfs.find("BIN/HELLO.ELF").unwrap();
+        assert_eq!(entry.metadata.file_name, "HELLO.ELF");
+        assert_eq!(entry.contents, &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut dir_iter = romfs.open_dir("BIN").unwrap();
+        let DirEntry::File(entry) = dir_iter.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
+        assert_eq!(entry.metadata.file_name, "HELLO.ELF");
+        assert!(dir_iter.next().is_none());
+
+        assert!(romfs.find("BIN/MISSING.ELF").is_none());
+        assert!(romfs.open_dir("MISSING").is_none());
+    }
+
+    #[test]
+    fn decode_long_filename() {
+        // A Version200 image containing one file whose name doesn't fit
+        // the 14-byte short field, so it's stored as a long-filename
+        // extension record - see EntryMetadata::LONG_NAME_FLAG.
+        let full_name = "VERY_LONG_FILENAME.TXT";
+        let short_name = &full_name[..14];
+
+        let mut image = std::vec::Vec::new();
+        image.extend_from_slice(b"NeoROMFS");
+        image.extend_from_slice(&[0x00, 0x02, 0x00, 0x00]);
+        // total size, patched in below
+        image.extend_from_slice(&[0u8; 4]);
+        // file tag, with the long-name flag set
+        image.push(EntryKind::File as u8 | 0x80);
+        // short (truncated) file name, already 14 bytes - no padding needed
+        image.extend_from_slice(short_name.as_bytes());
+        // file size
+        image.extend_from_slice(&3u32.to_be_bytes());
+        // file timestamp
+        image.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        // long-filename extension: 2-byte length, then the full name
+        image.extend_from_slice(&(full_name.len() as u16).to_be_bytes());
+        image.extend_from_slice(full_name.as_bytes());
+        // file contents
+        image.extend_from_slice(&[0x01, 0x02, 0x03]);
+
+        let total_size = image.len() as u32;
+        image[12..16].copy_from_slice(&total_size.to_be_bytes());
+
+        let romfs = RomFs::new(&image).unwrap();
+        let mut i = (&romfs).into_iter();
+        let DirEntry::File(item) = i.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
+        assert_eq!(item.metadata.file_name, full_name);
+        assert_eq!(item.contents, &[0x01, 0x02, 0x03]);
+        assert!(i.next().is_none());
+
+        assert_eq!(romfs.find(full_name).unwrap().contents, &[0x01, 0x02, 0x03]);
+    }
+
+    /// A tiny in-memory [`embedded_io::Read`] + [`embedded_io::Write`] +
+    /// [`embedded_io::Seek`] buffer, standing in for a real streaming
+    /// source/sink (e.g. a SPI flash driver) in the streaming round-trip
+    /// tests below.
+    struct Cursor {
+        buf: std::vec::Vec<u8>,
+        pos: usize,
+    }
+
+    impl Cursor {
+        fn new() -> Self {
+            Cursor {
+                buf: std::vec::Vec::new(),
+                pos: 0,
+            }
+        }
+
+        fn from_bytes(data: &[u8]) -> Self {
+            Cursor {
+                buf: data.to_vec(),
+                pos: 0,
+            }
+        }
+    }
+
+    impl embedded_io::ErrorType for Cursor {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Read for Cursor {
+        fn read(&mut self, out: &mut [u8]) -> Result<usize, Self::Error> {
+            let n = core::cmp::min(out.len(), self.buf.len() - self.pos);
+            out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    impl embedded_io::Write for Cursor {
+        fn write(&mut self, data: &[u8]) -> Result<usize, Self::Error> {
+            let end = self.pos + data.len();
+            if end > self.buf.len() {
+                self.buf.resize(end, 0);
+            }
+            self.buf[self.pos..end].copy_from_slice(data);
+            self.pos = end;
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl embedded_io::Seek for Cursor {
+        fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+            let new_pos = match pos {
+                embedded_io::SeekFrom::Start(n) => n as i64,
+                embedded_io::SeekFrom::End(n) => self.buf.len() as i64 + n,
+                embedded_io::SeekFrom::Current(n) => self.pos as i64 + n,
+            };
+            self.pos = new_pos as usize;
+            Ok(self.pos as u64)
+        }
+    }
+
+    fn dummy_metadata<'a>(file_name: &'a str, contents: &[u8]) -> EntryMetadata<&'a str> {
+        EntryMetadata {
+            file_name,
+            ctime: neotron_api::file::Time {
+                year_since_1970: 53,
+                zero_indexed_month: 10,
+                zero_indexed_day: 11,
+                hours: 20,
+                minutes: 5,
+                seconds: 16,
+            },
+            file_size: contents.len() as u32,
+            compression: Compression::None,
+            uncompressed_size: contents.len() as u32,
+            content_crc: crc32::crc32(contents),
+        }
+    }
+
+    #[test]
+    fn builder_round_trips_through_stream_reader() {
+        let readme = b"hello readme".as_slice();
+        let hello = b"hi!".as_slice();
+
+        let mut builder = RomFsBuilder::new(Cursor::new()).unwrap();
+        builder
+            .append(
+                &dummy_metadata("README.TXT", readme),
+                &mut Cursor::from_bytes(readme),
+            )
+            .unwrap();
+        builder
+            .append(
+                &dummy_metadata("HELLO.DOC", hello),
+                &mut Cursor::from_bytes(hello),
+            )
+            .unwrap();
+        let mut cursor = builder.finish().unwrap();
+        // `finish` leaves the cursor wherever it last patched the header,
+        // not rewound to the start - seek back before reading it as a
+        // fresh image.
+        cursor.seek(embedded_io::SeekFrom::Start(0)).unwrap();
+
+        let mut stream = StreamRomFs::from_reader(cursor).unwrap();
+        let mut entries = stream.entries();
+
+        let StreamEntry::File {
+            metadata,
+            content_len,
+            ..
+        } = entries.next().unwrap().unwrap()
+        else {
+            panic!("expected a file");
+        };
+        assert_eq!(metadata.file_name, "README.TXT");
+        assert_eq!(content_len, readme.len() as u32);
+        let entry = entries.next().unwrap().unwrap();
+        let StreamEntry::File { metadata, .. } = &entry else {
+            panic!("expected a file");
+        };
+        assert_eq!(metadata.file_name, "HELLO.DOC");
+        assert!(entries.next().is_none());
+
+        let mut out = [0u8; 3];
+        let n = entry.read_contents(&mut stream.reader, &mut out).unwrap();
+        assert_eq!(&out[..n], hello);
+    }
+
+    #[test]
+    fn builder_round_trips_through_verified_romfs() {
+        let readme = b"hello readme".as_slice();
+
+        let mut builder = RomFsBuilder::new(Cursor::new()).unwrap();
+        builder
+            .append(
+                &dummy_metadata("README.TXT", readme),
+                &mut Cursor::from_bytes(readme),
+            )
+            .unwrap();
+        let cursor = builder.finish().unwrap();
+
+        let romfs = RomFs::new_verified(&cursor.buf).unwrap();
+        let mut i = romfs.into_iter();
+        let DirEntry::File(entry) = i.next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
+        assert_eq!(entry.metadata.file_name, "README.TXT");
+        assert_eq!(entry.contents, readme);
+        assert!(entry.verify());
+        assert!(i.next().is_none());
+    }
+
+    #[test]
+    fn new_verified_rejects_corrupted_image() {
+        let readme = b"hello readme".as_slice();
+
+        let mut builder = RomFsBuilder::new(Cursor::new()).unwrap();
+        builder
+            .append(
+                &dummy_metadata("README.TXT", readme),
+                &mut Cursor::from_bytes(readme),
+            )
+            .unwrap();
+        let mut cursor = builder.finish().unwrap();
+
+        // Flip the last byte of the image, which falls within the stored
+        // file contents.
+        let corrupt_at = cursor.buf.len() - 1;
+        cursor.buf[corrupt_at] ^= 0xFF;
+
+        assert_eq!(
+            RomFs::new_verified(&cursor.buf).unwrap_err(),
+            Error::ChecksumMismatch
+        );
+        // The whole-image CRC check is opt-in - RomFs::new still mounts the
+        // (corrupted) image, it just won't catch the tampering itself.
+        let romfs = RomFs::new(&cursor.buf).unwrap();
+        let DirEntry::File(entry) = romfs.into_iter().next().unwrap().unwrap() else {
+            panic!("expected a file");
+        };
+        assert!(!entry.verify());
+    }
 }
 
 // End of file
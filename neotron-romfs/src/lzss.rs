@@ -0,0 +1,212 @@
+//! A small, table-free LZSS compressor/decompressor.
+//!
+//! Used to transparently shrink file contents stored in a ROMFS image (see
+//! [`crate::Compression::Lzss`]). The format is a stream of 8-bit flag
+//! groups: each flag bit, read LSB first, says whether the next item in the
+//! stream is a literal byte or a back-reference.
+//!
+//! A back-reference is two bytes: a 12-bit offset into a 4096-byte sliding
+//! window of already-decoded output, and a 4-bit length with an implied
+//! minimum match of 3, giving match lengths of 3 to 18 inclusive.
+
+use crate::Error;
+
+/// The size of the sliding window we search for matches in, and the
+/// largest distance a back-reference can encode.
+const WINDOW_SIZE: usize = 4096;
+/// The shortest run of bytes worth encoding as a back-reference.
+const MIN_MATCH: usize = 3;
+/// The longest run of bytes a single back-reference can encode.
+const MAX_MATCH: usize = MIN_MATCH + 0x0F;
+
+/// Compress `input` into `out`, returning the number of bytes written.
+///
+/// Returns [`Error::BufferTooSmall`] if `out` isn't large enough to hold
+/// the compressed stream.
+pub fn compress_into(input: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let mut out_pos = 0;
+    let mut pos = 0;
+
+    while pos < input.len() {
+        if out_pos >= out.len() {
+            return Err(Error::BufferTooSmall);
+        }
+        let flag_pos = out_pos;
+        out_pos += 1;
+        let mut flags = 0u8;
+
+        for bit in 0..8 {
+            if pos >= input.len() {
+                break;
+            }
+
+            match find_longest_match(input, pos) {
+                Some((distance, length)) => {
+                    if out_pos + 2 > out.len() {
+                        return Err(Error::BufferTooSmall);
+                    }
+                    // `distance` is in 1..=WINDOW_SIZE, so store it biased
+                    // by one to fit in 12 bits.
+                    let stored_distance = (distance - 1) as u16;
+                    out[out_pos] = (stored_distance & 0xFF) as u8;
+                    let distance_hi = ((stored_distance >> 8) & 0x0F) as u8;
+                    let length_nibble = (length - MIN_MATCH) as u8;
+                    out[out_pos + 1] = (distance_hi << 4) | length_nibble;
+                    out_pos += 2;
+                    pos += length;
+                    // flag bit for a back-reference is 0, already the default
+                }
+                None => {
+                    if out_pos >= out.len() {
+                        return Err(Error::BufferTooSmall);
+                    }
+                    out[out_pos] = input[pos];
+                    out_pos += 1;
+                    pos += 1;
+                    flags |= 1 << bit;
+                }
+            }
+        }
+
+        out[flag_pos] = flags;
+    }
+
+    Ok(out_pos)
+}
+
+/// Find the longest match for `input[pos..]` within the preceding
+/// [`WINDOW_SIZE`] bytes of `input`.
+///
+/// Returns `(distance, length)` where `distance` is how far back the match
+/// starts (`1` being the immediately preceding byte), or `None` if no match
+/// of at least [`MIN_MATCH`] bytes was found.
+fn find_longest_match(input: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let window_start = pos.saturating_sub(WINDOW_SIZE);
+    let max_len = core::cmp::min(MAX_MATCH, input.len() - pos);
+    if max_len < MIN_MATCH {
+        return None;
+    }
+
+    let mut best_len = 0;
+    let mut best_distance = 0;
+    for candidate in window_start..pos {
+        let mut len = 0;
+        while len < max_len && input[candidate + len] == input[pos + len] {
+            len += 1;
+        }
+        if len > best_len {
+            best_len = len;
+            best_distance = pos - candidate;
+        }
+    }
+
+    if best_len >= MIN_MATCH {
+        Some((best_distance, best_len))
+    } else {
+        None
+    }
+}
+
+/// Decompress `input` into `out`, returning the number of bytes written.
+///
+/// Returns [`Error::DecompressError`] if the stream is malformed, or refers
+/// to a back-reference that reaches further back than has been decoded so
+/// far, or would overflow `out`.
+pub fn decompress_into(input: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let mut in_pos = 0;
+    let mut out_pos = 0;
+
+    while in_pos < input.len() {
+        let flags = input[in_pos];
+        in_pos += 1;
+
+        for bit in 0..8 {
+            if in_pos >= input.len() {
+                break;
+            }
+            if (flags >> bit) & 1 == 1 {
+                // literal byte
+                if out_pos >= out.len() {
+                    return Err(Error::DecompressError);
+                }
+                out[out_pos] = input[in_pos];
+                in_pos += 1;
+                out_pos += 1;
+            } else {
+                // back-reference
+                let Some(&byte0) = input.get(in_pos) else {
+                    return Err(Error::DecompressError);
+                };
+                let Some(&byte1) = input.get(in_pos + 1) else {
+                    return Err(Error::DecompressError);
+                };
+                in_pos += 2;
+
+                let stored_distance = ((byte1 as usize & 0xF0) << 4) | byte0 as usize;
+                let distance = stored_distance + 1;
+                let length = (byte1 as usize & 0x0F) + MIN_MATCH;
+
+                if distance > out_pos {
+                    return Err(Error::DecompressError);
+                }
+                for _ in 0..length {
+                    if out_pos >= out.len() {
+                        return Err(Error::DecompressError);
+                    }
+                    // copied byte-by-byte so overlapping matches (where
+                    // `distance < length`) reproduce correctly
+                    out[out_pos] = out[out_pos - distance];
+                    out_pos += 1;
+                }
+            }
+        }
+    }
+
+    Ok(out_pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_no_repeats() {
+        let input = b"the quick brown fox";
+        let mut compressed = [0u8; 64];
+        let n = compress_into(input, &mut compressed).unwrap();
+        let mut output = [0u8; 64];
+        let m = decompress_into(&compressed[..n], &mut output).unwrap();
+        assert_eq!(&output[..m], input);
+    }
+
+    #[test]
+    fn roundtrip_with_repeats() {
+        let input = b"abcabcabcabcabcabcabcabcabcabcabc";
+        let mut compressed = [0u8; 64];
+        let n = compress_into(input, &mut compressed).unwrap();
+        let mut output = [0u8; 64];
+        let m = decompress_into(&compressed[..n], &mut output).unwrap();
+        assert_eq!(&output[..m], input);
+        // the repeats should actually have compressed
+        assert!(n < input.len());
+    }
+
+    #[test]
+    fn roundtrip_overlapping_match() {
+        // a run long enough that a back-reference will overlap its own output
+        let input = [0xAAu8; 32];
+        let mut compressed = [0u8; 64];
+        let n = compress_into(&input, &mut compressed).unwrap();
+        let mut output = [0u8; 64];
+        let m = decompress_into(&compressed[..n], &mut output).unwrap();
+        assert_eq!(&output[..m], &input[..]);
+    }
+
+    #[test]
+    fn decompress_truncated_errors() {
+        let mut output = [0u8; 8];
+        assert!(decompress_into(&[0x00, 0x01], &mut output).is_err());
+    }
+}
+
+// End of file